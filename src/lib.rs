@@ -0,0 +1,346 @@
+// Copyright 2015-2016 the slack-rs authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A Rust client for the [Slack Web API](https://api.slack.com/web).
+//!
+//! Each Slack method family lives in its own module (e.g. `team`), and
+//! every function there takes a `SlackWebRequestSender` so that the HTTP
+//! transport can be swapped out (for tests, for custom retry behavior,
+//! etc).
+
+extern crate rustc_serialize;
+#[cfg(feature = "tracing")]
+extern crate tracing;
+
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+
+use rustc_serialize::Decodable;
+use rustc_serialize::json;
+
+pub mod oauth;
+pub mod rate_limit;
+pub mod team;
+
+/// The result type returned by every Slack Web API call in this crate.
+pub type ApiResult<T> = Result<T, SlackError>;
+
+/// Errors that can occur while making a call against the Slack Web API.
+#[derive(Debug)]
+pub enum SlackError {
+    /// The underlying HTTP transport failed (connection error, timeout, etc).
+    Http(String),
+    /// The response body could not be parsed as the expected JSON shape.
+    MalformedResponse(json::DecoderError),
+    /// Slack responded with `"ok": false`; the string is the `error` field.
+    SlackApiError(String),
+    /// The transport received an HTTP 429 for this call; carries the
+    /// `Retry-After` header value in seconds, if Slack sent one.
+    RateLimited(Option<u64>),
+    /// A `RateLimitedSender` gave up retrying a rate-limited call.
+    RateLimitError(String),
+}
+
+impl fmt::Display for SlackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SlackError::Http(ref s) => write!(f, "http error: {}", s),
+            SlackError::MalformedResponse(ref e) => write!(f, "malformed response: {}", e),
+            SlackError::SlackApiError(ref s) => write!(f, "slack api error: {}", s),
+            SlackError::RateLimited(retry_after) => {
+                match retry_after {
+                    Some(secs) => write!(f, "rate limited, retry after {}s", secs),
+                    None => write!(f, "rate limited, retry after unknown delay"),
+                }
+            }
+            SlackError::RateLimitError(ref s) => write!(f, "rate limit retries exhausted: {}", s),
+        }
+    }
+}
+
+impl error::Error for SlackError {
+    fn description(&self) -> &str {
+        match *self {
+            SlackError::Http(ref s) => s,
+            SlackError::MalformedResponse(_) => "malformed response",
+            SlackError::SlackApiError(ref s) => s,
+            SlackError::RateLimited(_) => "rate limited",
+            SlackError::RateLimitError(ref s) => s,
+        }
+    }
+}
+
+/// Pagination info returned alongside list-style Slack responses.
+#[derive(Clone,Debug,RustcDecodable)]
+pub struct Pagination {
+    pub count: u32,
+    pub total: u32,
+    pub page: u32,
+    pub pages: u32,
+}
+
+/// Decodes the raw JSON body of a Slack Web API response into `T`,
+/// translating `"ok": false` into `SlackError::SlackApiError`.
+pub fn parse_slack_response<T: Decodable>(response: String) -> ApiResult<T> {
+    match json::Json::from_str(&response) {
+        Ok(json::Json::Object(ref obj)) => {
+            let ok = obj.get("ok").and_then(|o| o.as_boolean()).unwrap_or(false);
+            if !ok {
+                let err = obj.get("error")
+                    .and_then(|e| e.as_string())
+                    .unwrap_or("unknown_error")
+                    .to_string();
+                return Err(SlackError::SlackApiError(err));
+            }
+        }
+        _ => {}
+    }
+    json::decode(&response).map_err(SlackError::MalformedResponse)
+}
+
+/// Opens the `slack_api_call` span for `method`, if the `tracing` feature
+/// is enabled. Shared by `call`, `call_with_cookies` and
+/// `call_basic_auth` so the span shape (and the fact that the token is
+/// never one of its fields) only needs to be defined once.
+#[cfg(feature = "tracing")]
+fn start_call_span(method: &str) -> tracing::Span {
+    tracing::info_span!("slack_api_call",
+                         method = method,
+                         ok = tracing::field::Empty,
+                         status = tracing::field::Empty,
+                         err = tracing::field::Empty)
+}
+
+/// Records the outcome of a Slack API call onto `span`: whether `ok` was
+/// true, the inferred HTTP status, and (on failure) the `err` string.
+#[cfg(feature = "tracing")]
+fn record_call_outcome<T>(span: &tracing::Span, result: &ApiResult<T>) {
+    match *result {
+        Ok(_) => {
+            span.record("ok", &true);
+            span.record("status", &200u16);
+        }
+        Err(SlackError::SlackApiError(ref e)) => {
+            span.record("ok", &false);
+            span.record("status", &200u16);
+            span.record("err", &tracing::field::display(e));
+        }
+        Err(SlackError::RateLimited(_)) => {
+            span.record("ok", &false);
+            span.record("status", &429u16);
+        }
+        Err(ref e) => {
+            span.record("ok", &false);
+            span.record("err", &tracing::field::display(e));
+        }
+    }
+}
+
+/// Sends `method` via `client` and decodes the response into `T`.
+///
+/// This is the single choke point every Slack method function should
+/// route through: it calls `send_authed` and then `parse_slack_response`,
+/// and, when the `tracing` feature is enabled, wraps both in a span
+/// named `slack_api_call` carrying the method name and, once the call
+/// completes, whether Slack reported `ok` and (on failure) the `err`
+/// string. The token is never recorded on the span.
+pub fn call<R: SlackWebRequestSender, T: Decodable>(client: &R,
+                                                     method: &str,
+                                                     token: &str,
+                                                     params: HashMap<&str, &str>)
+                                                     -> ApiResult<T> {
+    #[cfg(feature = "tracing")]
+    let span = start_call_span(method);
+    #[cfg(feature = "tracing")]
+    let _enter = span.enter();
+
+    let result = client.send_authed(method, token, params)
+        .and_then(parse_slack_response);
+
+    #[cfg(feature = "tracing")]
+    record_call_outcome(&span, &result);
+
+    result
+}
+
+/// Like `call`, but authenticates with a browser-derived
+/// `SessionCredentials` (an `xoxc-` token plus its `d` cookie) instead of
+/// a bearer app token.
+pub fn call_with_cookies<R: SlackWebRequestSender, T: Decodable>(client: &R,
+                                                                  method: &str,
+                                                                  creds: &SessionCredentials,
+                                                                  params: HashMap<&str, &str>)
+                                                                  -> ApiResult<T> {
+    #[cfg(feature = "tracing")]
+    let span = start_call_span(method);
+    #[cfg(feature = "tracing")]
+    let _enter = span.enter();
+
+    let result = client.send_authed_with_cookies(method, creds, params)
+        .and_then(parse_slack_response);
+
+    #[cfg(feature = "tracing")]
+    record_call_outcome(&span, &result);
+
+    result
+}
+
+/// Like `call`, but authenticates with HTTP Basic auth (a client ID and
+/// secret) instead of a bearer token, for endpoints like `oauth.v2.access`
+/// that hand out the very token every other call in this crate needs.
+pub fn call_basic_auth<R: SlackWebRequestSender, T: Decodable>(client: &R,
+                                                                method: &str,
+                                                                client_id: &str,
+                                                                client_secret: &str,
+                                                                params: HashMap<&str, &str>)
+                                                                -> ApiResult<T> {
+    #[cfg(feature = "tracing")]
+    let span = start_call_span(method);
+    #[cfg(feature = "tracing")]
+    let _enter = span.enter();
+
+    let result = client.send_basic_auth(method, client_id, client_secret, params)
+        .and_then(parse_slack_response);
+
+    #[cfg(feature = "tracing")]
+    record_call_outcome(&span, &result);
+
+    result
+}
+
+/// Credentials for a browser-derived Slack session token (an `xoxc-...`
+/// token scraped from a logged-in browser session), which Slack only
+/// accepts when paired with that session's `d` cookie.
+///
+/// This is meant for self-serve/admin scraping scenarios where no
+/// installed app token is available; prefer a regular `xoxp`/`xoxb`
+/// token with `send_authed` wherever one exists.
+#[derive(Clone)]
+pub struct SessionCredentials {
+    pub token: String,
+    pub cookie: String,
+}
+
+impl SessionCredentials {
+    pub fn new(token: &str, cookie: &str) -> SessionCredentials {
+        SessionCredentials {
+            token: token.to_string(),
+            cookie: cookie.to_string(),
+        }
+    }
+}
+
+impl fmt::Debug for SessionCredentials {
+    /// Redacted so the live `xoxc-` token and `d` cookie never end up in
+    /// logs or panic messages.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SessionCredentials")
+            .field("token", &"<redacted>")
+            .field("cookie", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Abstraction over sending an authenticated request to the Slack Web API.
+///
+/// Implementations are free to use whatever HTTP client they like; the
+/// crate ships a mock implementation under `test_helpers` for use in
+/// unit tests.
+pub trait SlackWebRequestSender {
+    /// Sends `method` with a bearer `token` and the given form `params`,
+    /// returning the raw response body.
+    fn send_authed(&self, method: &str, token: &str, params: HashMap<&str, &str>) -> ApiResult<String>;
+
+    /// Like `send_authed`, but for `xoxc-` browser session tokens: attaches
+    /// `creds.cookie` via a `Cookie: d=...` header alongside the bearer
+    /// token.
+    ///
+    /// The default implementation errors out rather than silently
+    /// forwarding to `send_authed` (which would drop the cookie and send
+    /// a request Slack will reject); transports that want to support
+    /// `xoxc` session credentials should override it.
+    fn send_authed_with_cookies(&self,
+                                 _method: &str,
+                                 _creds: &SessionCredentials,
+                                 _params: HashMap<&str, &str>)
+                                 -> ApiResult<String> {
+        Err(SlackError::Http("this SlackWebRequestSender does not support xoxc session \
+                               credentials; override send_authed_with_cookies"
+            .to_string()))
+    }
+
+    /// Sends `method` with `Authorization: Basic` credentials built from
+    /// `client_id`/`client_secret` instead of a bearer token, for
+    /// endpoints like `oauth.v2.access` that are called before a token
+    /// exists.
+    ///
+    /// The default implementation errors out rather than silently
+    /// forwarding to `send_authed` (which has no bearer token to send);
+    /// transports that want to support the OAuth exchange should
+    /// override it.
+    fn send_basic_auth(&self,
+                        _method: &str,
+                        _client_id: &str,
+                        _client_secret: &str,
+                        _params: HashMap<&str, &str>)
+                        -> ApiResult<String> {
+        Err(SlackError::Http("this SlackWebRequestSender does not support HTTP Basic auth; \
+                               override send_basic_auth"
+            .to_string()))
+    }
+}
+
+#[cfg(test)]
+pub mod test_helpers {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    use super::{ApiResult, SessionCredentials, SlackWebRequestSender};
+
+    /// A `SlackWebRequestSender` that always returns a canned response,
+    /// for use in unit tests.
+    pub struct MockSlackWebRequestSender {
+        response: RefCell<String>,
+    }
+
+    impl MockSlackWebRequestSender {
+        pub fn respond_with(response: &str) -> MockSlackWebRequestSender {
+            MockSlackWebRequestSender { response: RefCell::new(response.to_string()) }
+        }
+    }
+
+    impl SlackWebRequestSender for MockSlackWebRequestSender {
+        fn send_authed(&self, _method: &str, _token: &str, _params: HashMap<&str, &str>) -> ApiResult<String> {
+            Ok(self.response.borrow().clone())
+        }
+
+        fn send_authed_with_cookies(&self,
+                                     _method: &str,
+                                     _creds: &SessionCredentials,
+                                     _params: HashMap<&str, &str>)
+                                     -> ApiResult<String> {
+            Ok(self.response.borrow().clone())
+        }
+
+        fn send_basic_auth(&self,
+                            _method: &str,
+                            _client_id: &str,
+                            _client_secret: &str,
+                            _params: HashMap<&str, &str>)
+                            -> ApiResult<String> {
+            Ok(self.response.borrow().clone())
+        }
+    }
+}
@@ -0,0 +1,233 @@
+// Copyright 2015-2016 the slack-rs authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A `SlackWebRequestSender` decorator that throttles and retries calls
+//! according to [Slack's per-method rate limit
+//! tiers](https://api.slack.com/docs/rate-limits).
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::{ApiResult, SessionCredentials, SlackError, SlackWebRequestSender};
+
+/// One of Slack's four rate limit tiers.
+///
+/// The per-minute numbers are Slack's documented *approximate*
+/// allowances; Slack does not publish exact bucket sizes, so these are
+/// used as a conservative refill rate rather than a hard guarantee.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum Tier {
+    One,
+    Two,
+    Three,
+    Four,
+}
+
+impl Tier {
+    fn per_minute(&self) -> u32 {
+        match *self {
+            Tier::One => 1,
+            Tier::Two => 20,
+            Tier::Three => 50,
+            Tier::Four => 100,
+        }
+    }
+}
+
+/// Looks up the rate limit tier for a Slack Web API method name.
+///
+/// Methods not yet listed here default to `Tier::Four`, the least
+/// restrictive tier, rather than refusing to send the request.
+fn tier_for_method(method: &str) -> Tier {
+    match method {
+        "team.accessLogs" => Tier::Two,
+        "team.info" => Tier::Three,
+        "team.billableInfo" => Tier::Three,
+        "team.integrationLogs" => Tier::Two,
+        _ => Tier::Four,
+    }
+}
+
+/// A simple token bucket that refills continuously at a tier's rate.
+struct TokenBucket {
+    tokens: f64,
+    capacity: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn for_tier(tier: Tier) -> TokenBucket {
+        let capacity = tier.per_minute() as f64;
+        TokenBucket {
+            tokens: capacity,
+            capacity: capacity,
+            refill_per_sec: capacity / 60.0,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill);
+        let elapsed_secs = elapsed.as_secs() as f64 + (elapsed.subsec_nanos() as f64 / 1_000_000_000.0);
+        self.tokens = (self.tokens + elapsed_secs * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    /// Blocks the current thread until a token is available, then takes it.
+    fn acquire(&mut self) {
+        loop {
+            self.refill();
+            if self.tokens >= 1.0 {
+                self.tokens -= 1.0;
+                return;
+            }
+            let deficit = 1.0 - self.tokens;
+            let wait_ms = ((deficit / self.refill_per_sec) * 1000.0) as u64 + 1;
+            thread::sleep(Duration::from_millis(wait_ms));
+        }
+    }
+}
+
+/// Wraps a `SlackWebRequestSender` with per-method token-bucket
+/// throttling, and automatically retries HTTP 429 responses by sleeping
+/// the `Retry-After` duration Slack sends back.
+///
+/// Bucket state is kept behind a `Mutex`, so a single
+/// `RateLimitedSender` can be shared across threads (e.g. wrapped in an
+/// `Arc`).
+///
+/// Each method gets its own `Mutex<TokenBucket>`; the outer map `Mutex`
+/// is only held long enough to fetch or insert that per-method bucket,
+/// never across the (potentially minutes-long, for `Tier::One` methods)
+/// blocking wait inside `acquire()`. Otherwise one slow-tier call would
+/// serialize every other thread's calls, on any tier, behind the same
+/// lock.
+pub struct RateLimitedSender<R: SlackWebRequestSender> {
+    inner: R,
+    buckets: Mutex<HashMap<String, Arc<Mutex<TokenBucket>>>>,
+    max_retries: u32,
+}
+
+impl<R: SlackWebRequestSender> RateLimitedSender<R> {
+    /// Wraps `inner`, retrying 429 responses up to `max_retries` times
+    /// before giving up with `SlackError::RateLimitError`.
+    pub fn new(inner: R, max_retries: u32) -> RateLimitedSender<R> {
+        RateLimitedSender {
+            inner: inner,
+            buckets: Mutex::new(HashMap::new()),
+            max_retries: max_retries,
+        }
+    }
+
+    fn acquire_token(&self, method: &str) {
+        let bucket = {
+            let mut buckets = self.buckets.lock().unwrap();
+            buckets.entry(method.to_string())
+                .or_insert_with(|| Arc::new(Mutex::new(TokenBucket::for_tier(tier_for_method(method)))))
+                .clone()
+        };
+        bucket.lock().unwrap().acquire();
+    }
+}
+
+impl<R: SlackWebRequestSender> SlackWebRequestSender for RateLimitedSender<R> {
+    fn send_authed(&self, method: &str, token: &str, params: HashMap<&str, &str>) -> ApiResult<String> {
+        let mut attempts = 0;
+        loop {
+            self.acquire_token(method);
+            match self.inner.send_authed(method, token, params.clone()) {
+                Err(SlackError::RateLimited(retry_after)) => {
+                    if attempts >= self.max_retries {
+                        return Err(SlackError::RateLimitError(
+                            format!("exhausted {} retries for {}", self.max_retries, method)));
+                    }
+                    attempts += 1;
+                    thread::sleep(Duration::from_secs(retry_after.unwrap_or(1)));
+                }
+                other => return other,
+            }
+        }
+    }
+
+    fn send_authed_with_cookies(&self,
+                                 method: &str,
+                                 creds: &SessionCredentials,
+                                 params: HashMap<&str, &str>)
+                                 -> ApiResult<String> {
+        let mut attempts = 0;
+        loop {
+            self.acquire_token(method);
+            match self.inner.send_authed_with_cookies(method, creds, params.clone()) {
+                Err(SlackError::RateLimited(retry_after)) => {
+                    if attempts >= self.max_retries {
+                        return Err(SlackError::RateLimitError(
+                            format!("exhausted {} retries for {}", self.max_retries, method)));
+                    }
+                    attempts += 1;
+                    thread::sleep(Duration::from_secs(retry_after.unwrap_or(1)));
+                }
+                other => return other,
+            }
+        }
+    }
+
+    fn send_basic_auth(&self,
+                        method: &str,
+                        client_id: &str,
+                        client_secret: &str,
+                        params: HashMap<&str, &str>)
+                        -> ApiResult<String> {
+        let mut attempts = 0;
+        loop {
+            self.acquire_token(method);
+            match self.inner.send_basic_auth(method, client_id, client_secret, params.clone()) {
+                Err(SlackError::RateLimited(retry_after)) => {
+                    if attempts >= self.max_retries {
+                        return Err(SlackError::RateLimitError(
+                            format!("exhausted {} retries for {}", self.max_retries, method)));
+                    }
+                    attempts += 1;
+                    thread::sleep(Duration::from_secs(retry_after.unwrap_or(1)));
+                }
+                other => return other,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use super::super::test_helpers::MockSlackWebRequestSender;
+
+    #[test]
+    fn passes_through_successful_response() {
+        let inner = MockSlackWebRequestSender::respond_with(r#"{"ok": true}"#);
+        let sender = RateLimitedSender::new(inner, 3);
+        let result = sender.send_authed("team.info", "TEST_TOKEN", HashMap::new());
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn unknown_methods_default_to_tier_four() {
+        assert_eq!(tier_for_method("chat.postMessage"), Tier::Four);
+        assert_eq!(tier_for_method("team.info"), Tier::Three);
+    }
+}
@@ -17,7 +17,7 @@
 
 use std::collections::HashMap;
 
-use super::{ApiResult, SlackWebRequestSender, parse_slack_response};
+use super::{ApiResult, SessionCredentials, SlackWebRequestSender};
 
 /// Gets the access logs for the current team.
 ///
@@ -32,8 +32,25 @@ pub fn access_logs<R: SlackWebRequestSender>(client: &R, token: &str, count: Opt
     if let Some(ref page) = page {
         params.insert("page", page);
     }
-    let response = try!(client.send_authed("team.accessLogs", token, params));
-    parse_slack_response(response, true)
+    super::call(client, "team.accessLogs", token, params)
+}
+
+/// Like `access_logs`, but authenticates with a browser-derived `xoxc-`
+/// session token and its companion `d` cookie, for self-serve/admin
+/// scraping scenarios where no app token is available.
+///
+/// Wraps https://api.slack.com/methods/team.accessLogs
+pub fn access_logs_with_session<R: SlackWebRequestSender>(client: &R, creds: &SessionCredentials, count: Option<u32>, page: Option<u32>) -> ApiResult<AccessLogsResponse> {
+    let count = count.map(|c| c.to_string());
+    let page = page.map(|p| p.to_string());
+    let mut params: HashMap<&str, &str> = HashMap::new();
+    if let Some(ref count) = count {
+        params.insert("count", count);
+    }
+    if let Some(ref page) = page {
+        params.insert("page", page);
+    }
+    super::call_with_cookies(client, "team.accessLogs", creds, params)
 }
 
 #[derive(Clone,Debug,RustcDecodable)]
@@ -60,8 +77,16 @@ pub struct AccessLogsResponse {
 ///
 /// Wraps https://api.slack.com/methods/team.info
 pub fn info<R: SlackWebRequestSender>(client: &R, token: &str) -> ApiResult<InfoResponse> {
-    let response = try!(client.send_authed("team.info", token, HashMap::new()));
-    parse_slack_response(response, true)
+    super::call(client, "team.info", token, HashMap::new())
+}
+
+/// Like `info`, but authenticates with a browser-derived `xoxc-` session
+/// token and its companion `d` cookie, for self-serve/admin scraping
+/// scenarios where no app token is available.
+///
+/// Wraps https://api.slack.com/methods/team.info
+pub fn info_with_session<R: SlackWebRequestSender>(client: &R, creds: &SessionCredentials) -> ApiResult<InfoResponse> {
+    super::call_with_cookies(client, "team.info", creds, HashMap::new())
 }
 
 #[derive(Clone,Debug,RustcDecodable)]
@@ -89,6 +114,82 @@ pub struct InfoResponse {
     pub team: TeamInfo,
 }
 
+/// Gets billing-active status for users on the team.
+///
+/// Wraps https://api.slack.com/methods/team.billableInfo
+pub fn billable_info<R: SlackWebRequestSender>(client: &R, token: &str, user: Option<&str>) -> ApiResult<BillableInfoResponse> {
+    let mut params: HashMap<&str, &str> = HashMap::new();
+    if let Some(user) = user {
+        params.insert("user", user);
+    }
+    super::call(client, "team.billableInfo", token, params)
+}
+
+#[derive(Clone,Debug,RustcDecodable)]
+pub struct BillableUser {
+    pub billing_active: bool,
+}
+
+#[derive(Clone,Debug,RustcDecodable)]
+pub struct BillableInfoResponse {
+    pub billable_info: HashMap<String, BillableUser>,
+}
+
+/// Gets the audit trail of changes to the team's installed integrations.
+///
+/// Wraps https://api.slack.com/methods/team.integrationLogs
+pub fn integration_logs<R: SlackWebRequestSender>(client: &R,
+                                                    token: &str,
+                                                    service_id: Option<&str>,
+                                                    app_id: Option<&str>,
+                                                    user: Option<&str>,
+                                                    change_type: Option<&str>,
+                                                    count: Option<u32>,
+                                                    page: Option<u32>)
+                                                    -> ApiResult<IntegrationLogsResponse> {
+    let count = count.map(|c| c.to_string());
+    let page = page.map(|p| p.to_string());
+    let mut params: HashMap<&str, &str> = HashMap::new();
+    if let Some(service_id) = service_id {
+        params.insert("service_id", service_id);
+    }
+    if let Some(app_id) = app_id {
+        params.insert("app_id", app_id);
+    }
+    if let Some(user) = user {
+        params.insert("user", user);
+    }
+    if let Some(change_type) = change_type {
+        params.insert("change_type", change_type);
+    }
+    if let Some(ref count) = count {
+        params.insert("count", count);
+    }
+    if let Some(ref page) = page {
+        params.insert("page", page);
+    }
+    super::call(client, "team.integrationLogs", token, params)
+}
+
+#[derive(Clone,Debug,RustcDecodable)]
+pub struct IntegrationLogEntry {
+    pub service_id: Option<String>,
+    pub service_type: Option<String>,
+    pub user_id: String,
+    pub user_name: String,
+    pub app_id: Option<String>,
+    pub app_type: Option<String>,
+    pub date: String,
+    pub change_type: String,
+    pub scope: String,
+}
+
+#[derive(Clone,Debug,RustcDecodable)]
+pub struct IntegrationLogsResponse {
+    pub logs: Vec<IntegrationLogEntry>,
+    pub paging: super::Pagination,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -147,6 +248,25 @@ mod tests {
         assert_eq!(result.logins[1].username, "alice");
     }
 
+    #[test]
+    fn access_logs_with_session_ok_response() {
+        let client = MockSlackWebRequestSender::respond_with(r#"{
+            "ok": true,
+            "logins": [],
+            "paging": {
+                "count": 100,
+                "total": 0,
+                "page": 1,
+                "pages": 1
+            }
+        }"#);
+        let creds = SessionCredentials::new("xoxc-TEST_TOKEN", "TEST_COOKIE");
+        let result = access_logs_with_session(&client, &creds, None, None);
+        if let Err(err) = result {
+            panic!(format!("{:?}", err));
+        }
+    }
+
     #[test]
     fn info_ok_response() {
         let client = MockSlackWebRequestSender::respond_with(r#"{
@@ -175,4 +295,89 @@ mod tests {
         assert_eq!(result.team.name, "My Team");
         assert_eq!(result.team.icon.image_default, true);
     }
+
+    #[test]
+    fn info_with_session_ok_response() {
+        let client = MockSlackWebRequestSender::respond_with(r#"{
+            "ok": true,
+            "team": {
+                "id": "T12345",
+                "name": "My Team",
+                "domain": "example",
+                "email_domain": "",
+                "icon": {
+                    "image_34": "https:\/\/...",
+                    "image_44": "https:\/\/...",
+                    "image_68": "https:\/\/...",
+                    "image_88": "https:\/\/...",
+                    "image_102": "https:\/\/...",
+                    "image_132": "https:\/\/...",
+                    "image_default": true
+                }
+            }
+        }"#);
+        let creds = SessionCredentials::new("xoxc-TEST_TOKEN", "TEST_COOKIE");
+        let result = info_with_session(&client, &creds);
+        if let Err(err) = result {
+            panic!(format!("{:?}", err));
+        }
+        let result = result.unwrap();
+        assert_eq!(result.team.name, "My Team");
+        assert_eq!(result.team.icon.image_default, true);
+    }
+
+    #[test]
+    fn billable_info_ok_response() {
+        let client = MockSlackWebRequestSender::respond_with(r#"{
+            "ok": true,
+            "billable_info": {
+                "U12345": {
+                    "billing_active": true
+                },
+                "U45678": {
+                    "billing_active": false
+                }
+            }
+        }"#);
+        let result = billable_info(&client, "TEST_TOKEN", None);
+        if let Err(err) = result {
+            panic!(format!("{:?}", err));
+        }
+        let result = result.unwrap();
+        assert_eq!(result.billable_info["U12345"].billing_active, true);
+        assert_eq!(result.billable_info["U45678"].billing_active, false);
+    }
+
+    #[test]
+    fn integration_logs_ok_response() {
+        let client = MockSlackWebRequestSender::respond_with(r#"{
+            "ok": true,
+            "logs": [
+                {
+                    "service_id": "1234567890",
+                    "service_type": "Google Calendar",
+                    "user_id": "U12345",
+                    "user_name": "bob",
+                    "app_id": null,
+                    "app_type": null,
+                    "date": "1392163904",
+                    "change_type": "enabled",
+                    "scope": "incoming-webhook"
+                }
+            ],
+            "paging": {
+                "count": 100,
+                "total": 1,
+                "page": 1,
+                "pages": 1
+            }
+        }"#);
+        let result = integration_logs(&client, "TEST_TOKEN", None, None, None, None, None, None);
+        if let Err(err) = result {
+            panic!(format!("{:?}", err));
+        }
+        let result = result.unwrap();
+        assert_eq!(result.logs[0].user_name, "bob");
+        assert_eq!(result.logs[0].change_type, "enabled");
+    }
 }